@@ -3,11 +3,14 @@
 //! Tools for manipulating formatted data representing information about a
 //! collection of music, using ASCII files as storage.
 
-use std::io::{BufReader,BufRead};
-use std::fs::File;
+use std::io::{BufReader,BufRead,Write};
+use std::fs::{self,File};
+use std::path::Path;
 use regex::Regex;
 use str;
 use custom_error::custom_error;
+use lofty::{Accessor,AudioFile,ItemKey,Probe,TaggedFileExt};
+use walkdir::WalkDir;
 
 
 
@@ -15,13 +18,142 @@ custom_error!{SearchError
     not_found = "Couldn't find the desired item."
 }
 
+custom_error!{pub ParseError
+    line{number: usize, reason: String} = "line {number}: {reason}"
+}
+
+
+
+/// Parses a track-position tag written as "item/total" (e.g. "3/12"),
+/// returning just the track number and discarding the album size.
+fn parse_track_position(raw: &str) -> Option<u16>{
+    return raw.split('/').next()?.trim().parse().ok();
+}
+
+/// Joins every value present for a repeated tag field (e.g. multiple
+/// album-artist entries) into one logical value.
+fn join_repeated_values(values: &[String]) -> Option<String>{
+    if values.is_empty(){
+        return None;
+    }
+    return Some(values.join("; "));
+}
+
+/// Extensions recognised as audio files by `Collection::from_audio_dir`
+/// and `Collection::scan_library_with_depth`.
+const AUDIO_EXTENSIONS: [&str; 4] = ["mp3","flac","m4a","ogg"];
+
+/// Returns whether `path` has one of `AUDIO_EXTENSIONS`, case-insensitively.
+fn is_audio_file(path: &Path) -> bool{
+    let ext = path.extension()
+      .and_then(|e| e.to_str())
+      .unwrap_or("")
+      .to_lowercase();
+    return AUDIO_EXTENSIONS.contains(&ext.as_str());
+}
+
+/// The title/artist/album/track/duration tags read out of a single audio
+/// file.
+struct Tags{
+    title: Option<String>,
+    artist: Option<String>,
+    album: Option<String>,
+    album_artist: Option<String>,
+    track: Option<u16>,
+    length: u16
+}
+
+/// Reads title/artist/album/track/duration tags from an audio file,
+/// regardless of its underlying format (mp3, flac, m4a, ogg all go through
+/// the same `lofty` probe). Any field absent from the file's tags is left
+/// as `None`; callers are expected to fall back to something sensible (e.g.
+/// the filename for a missing title). `length` is `0` when the file can't
+/// be read.
+///
+/// `artist` is the per-track artist; `album_artist` is the tag meant to
+/// group tracks into an album, joined from any repeated album-artist
+/// entries into a single logical value. The track number is read from a
+/// raw "item/total" position tag (e.g. "3/12"), keeping only the track
+/// number.
+fn read_tags(path: &Path) -> Tags{
+    let mut title: Option<String> = None;
+    let mut artist: Option<String> = None;
+    let mut album: Option<String> = None;
+    let mut album_artist: Option<String> = None;
+    let mut track: Option<u16> = None;
+    let mut length: u16 = 0;
+
+    if let Ok(tagged_file) = Probe::open(path).and_then(|p| p.read()){
+        length = tagged_file.properties().duration().as_secs() as u16;
+        if let Some(tag) = tagged_file.primary_tag(){
+            title = tag.title().map(|s| s.to_string());
+            album = tag.album().map(|s| s.to_string());
+            artist = tag.artist().map(|s| s.to_string());
+
+            let album_artists: Vec<String> = tag.get_strings(&ItemKey::AlbumArtist)
+              .map(|s| s.to_string())
+              .collect();
+            album_artist = join_repeated_values(&album_artists);
 
+            track = tag.get_string(&ItemKey::TrackNumber)
+              .and_then(parse_track_position);
+        }
+    }
+
+    return Tags{title, artist, album, album_artist, track, length};
+}
 
 /// Holds a single Song.
 struct Song{
     name: String,
     artist: String,
-    length: u16
+    length: u16,
+    track: Option<u16>,
+    lyrics: Option<Vec<(u32, String)>>
+}
+
+/// Parses a single LRC time tag of the form "mm:ss.xx" into centiseconds.
+fn lrc_tag_to_centiseconds(tag: &str) -> Option<u32>{
+    let parts: Vec<&str> = tag.splitn(2, ':').collect();
+    let minutes: u32 = parts.get(0)?.parse().ok()?;
+    let sec_parts: Vec<&str> = parts.get(1)?.splitn(2, '.').collect();
+    let seconds: u32 = sec_parts.get(0)?.parse().ok()?;
+    let centiseconds: u32 = match sec_parts.get(1){
+        // LRC fractions aren't always two digits (e.g. enhanced/millisecond
+        // tags like ".5" or ".500"); normalize to hundredths by padding a
+        // short fraction with zeros and truncating a longer one.
+        Some(frac) => {
+            let mut hundredths = frac.to_string();
+            hundredths.truncate(2);
+            while hundredths.len() < 2{
+                hundredths.push('0');
+            }
+            hundredths.parse().ok()?
+        },
+        None => 0
+    };
+    return Some(minutes * 6000 + seconds * 100 + centiseconds);
+}
+
+/// An Album's release date.
+///
+/// `month` and `day` are `0` when the Album's date is only known to the
+/// year, which also sorts those entries before dated releases in the same
+/// year (see `Collection::sort_albums`).
+struct ReleaseDate{
+    year: u16,
+    month: u8,
+    day: u8
+}
+
+/// Parses a release date of the form "YYYY", "YYYY-MM", or "YYYY-MM-DD".
+/// Any component missing or unparsable defaults to `0`.
+fn parse_release_date(s: &str) -> ReleaseDate{
+    let parts: Vec<&str> = s.split('-').collect();
+    let year: u16 = parts.get(0).and_then(|p| p.parse().ok()).unwrap_or(0);
+    let month: u8 = parts.get(1).and_then(|p| p.parse().ok()).unwrap_or(0);
+    let day: u8 = parts.get(2).and_then(|p| p.parse().ok()).unwrap_or(0);
+    return ReleaseDate{year, month, day};
 }
 
 /// Holds a single Album.
@@ -30,7 +162,8 @@ struct Song{
 struct Album{
     name: String,
     artist: String,
-    songs: Vec<Song>
+    songs: Vec<Song>,
+    release_date: Option<ReleaseDate>
 }
 
 /// Holds several Albums and allows for public interaction with them.
@@ -48,7 +181,10 @@ pub struct Collection{
 
 impl Song{
     fn display(&self){
-        print!("{} : {}\n", self.name, to_timestamp(self.length));
+        match self.track{
+            Some(track) => print!("{}. {} : {}\n", track, self.name, to_timestamp(self.length)),
+            None => print!("{} : {}\n", self.name, to_timestamp(self.length))
+        }
     }
 
 
@@ -57,10 +193,93 @@ impl Song{
         let x = Song{
             name: n,
             artist: a,
-            length: l
+            length: l,
+            track: None,
+            lyrics: None
         };
         return x;
     }
+
+    /// Formats this Song as an `#EXTINF` entry for an extended M3U playlist.
+    ///
+    /// A `length` of `0` means the duration is unknown (e.g. tag reading
+    /// failed), and is written as `-1` per the extended-M3U convention.
+    fn to_m3u_entry(&self) -> String{
+        let duration: i32 = if self.length == 0{ -1 }else{ self.length as i32 };
+        format!("#EXTINF:{},{} - {}\n{}\n", duration, self.artist, self.name, self.name)
+    }
+
+    /// Reads an LRC lyrics file and attaches its timed lines to this Song.
+    ///
+    /// Each LRC line may carry one or more `[mm:ss.xx]` time tags ahead of
+    /// its lyric text; every tag on a line produces its own entry sharing
+    /// that text. Non-numeric tags such as `[ti:]`, `[ar:]`, `[al:]` are
+    /// metadata, not timestamps, and are left out of the result, which is
+    /// sorted by timestamp.
+    ///
+    /// An unreadable line is skipped rather than aborting the whole file;
+    /// every such line is collected into the returned `Err` alongside its
+    /// line number, mirroring `Collection::parseFile`. `Ok(())` means every
+    /// line read cleanly.
+    pub fn load_lrc(&mut self, path: String) -> Result<(), Vec<ParseError>>{
+        let opened = File::open(&path).map_err(|e| vec![ParseError::line{
+            number: 0,
+            reason: format!("couldn't open '{}': {}", path, e)
+        }])?;
+        let file = BufReader::new(opened);
+        let tag_re = Regex::new(r"\[(\d+:\d+(?:\.\d+)?)\]").unwrap();
+        let mut lyrics: Vec<(u32, String)> = Vec::new();
+        let mut errors: Vec<ParseError> = Vec::new();
+
+        for (i, line) in file.lines().enumerate(){
+            let number = i + 1;
+            let next_line = match line{
+                Ok(l) => l,
+                Err(e) => {
+                    errors.push(ParseError::line{number, reason: e.to_string()});
+                    continue;
+                }
+            };
+            let timestamps: Vec<u32> = tag_re.captures_iter(&next_line)
+              .filter_map(|c| lrc_tag_to_centiseconds(&c[1]))
+              .collect();
+            if timestamps.is_empty(){
+                continue;
+            }
+            let text = tag_re.replace_all(&next_line, "").trim().to_string();
+            for timestamp in timestamps{
+                lyrics.push((timestamp, text.clone()));
+            }
+        }
+
+        lyrics.sort_by_key(|(timestamp, _)| *timestamp);
+        self.lyrics = Some(lyrics);
+
+        if errors.is_empty(){
+            return Ok(());
+        }
+        return Err(errors);
+    }
+
+    /// Prints every loaded lyric line alongside its timestamp.
+    pub fn display_lyrics(&self){
+        if let Some(lyrics) = &self.lyrics{
+            for (timestamp, text) in lyrics{
+                print!("{} : {}\n", to_timestamp((*timestamp / 100) as u16), text);
+            }
+        }
+    }
+
+    /// Returns the lyric line active at `position` seconds into playback,
+    /// i.e. the last loaded line whose timestamp is at or before `position`.
+    pub fn lyric_at(&self, position: f32) -> Option<&str>{
+        let centiseconds = (position * 100.0) as u32;
+        let lyrics = self.lyrics.as_ref()?;
+        return lyrics.iter()
+          .filter(|(timestamp, _)| *timestamp <= centiseconds)
+          .last()
+          .map(|(_, text)| text.as_str());
+    }
 }
 
 impl Album{
@@ -85,15 +304,30 @@ impl Album{
     }
 
     
-    /// Constructs a new Album.
+    /// Constructs a new Album, with no release date set.
     fn new(n: String, a: String) -> Album{
         let new = Album{
             name: n,
             artist: a,
-            songs: Vec::new()
+            songs: Vec::new(),
+            release_date: None
         };
         return new;
     }
+
+    /// Writes this Album out as an extended M3U playlist file.
+    ///
+    /// See `Collection::export_m3u` for the format written. Returns an
+    /// `Err` if `path` can't be created or written to, rather than
+    /// panicking.
+    pub fn export_m3u(&self, path: String) -> std::io::Result<()>{
+        let mut file = File::create(&path)?;
+        write!(file, "#EXTM3U\n")?;
+        for song in &self.songs{
+            write!(file, "{}", song.to_m3u_entry())?;
+        }
+        return Ok(());
+    }
 }
 
 impl Collection{
@@ -109,12 +343,20 @@ impl Collection{
     }
 
     /// Parses the file at the given path and adds its contents to this
-    /// Collection, provided it is formatted correctly.
-    /// 
+    /// Collection.
+    ///
     /// The file must be formatted as a list of songs separated by Album
     /// titles/artists. For more information about the correct way of
     /// formatting these files, refer to the example content file 'Albums.txt'.
-    /// 
+    /// An Album header may optionally carry a release date as a third
+    /// field, e.g. `Artist : Album : 2021-03`; a date is otherwise left
+    /// unset.
+    ///
+    /// A malformed row (an unparsable duration, or a header or song line
+    /// missing a field) is skipped rather than aborting the whole import;
+    /// every such row is collected into the returned `Err` alongside its
+    /// line number. `Ok(())` means every row parsed cleanly.
+    ///
     /// # Examples
     /// 
     /// For the below appropriately formatted file 'Albums.txt'
@@ -135,35 +377,78 @@ impl Collection{
     /// let collection: Collection = Collection::new();
     /// collection.parseFile("Albums.txt");
     /// ```
-    pub fn parseFile(&mut self, path: String){
-        let file = BufReader::new(File::open(&path).unwrap());
+    pub fn parseFile(&mut self, path: String) -> Result<(), Vec<ParseError>>{
+        let opened = File::open(&path).map_err(|e| vec![ParseError::line{
+            number: 0,
+            reason: format!("couldn't open '{}': {}", path, e)
+        }])?;
+        let file = BufReader::new(opened);
+        let mut errors: Vec<ParseError> = Vec::new();
 
         let mut album: Album = Album::new(
           String::from("DEFAULT"),
           String::from("DEFALT")
         );
-        for line in file.lines(){
-            let nextLine: String = line.unwrap();
-            if (Regex::new(r" - ").unwrap().is_match(&nextLine)){
+        for (i, line) in file.lines().enumerate(){
+            let number = i + 1;
+            let nextLine: String = match line{
+                Ok(l) => l,
+                Err(e) => {
+                    errors.push(ParseError::line{number, reason: e.to_string()});
+                    continue;
+                }
+            };
+
+            if Regex::new(r" - ").unwrap().is_match(&nextLine){
                 let data: Vec<&str> =
                   Regex::new(r"\s{1}-\s{1}").unwrap()
                   .split(&nextLine).collect();
+                if data.len() < 2{
+                    errors.push(ParseError::line{
+                        number,
+                        reason: format!("expected '<duration> - <name>', got '{}'", nextLine)
+                    });
+                    continue;
+                }
+                let length = match to_seconds(data[0]){
+                    Ok(l) => l,
+                    Err(reason) => {
+                        errors.push(ParseError::line{number, reason});
+                        continue;
+                    }
+                };
                 let song: Song = Song::new(
                   data[1].to_string(),
                   String::from("DEFALT"),
-                  to_seconds(data[0])
+                  length
                 );
                 album.add(song);
             }else{
                 self.add(album);
                 let data: Vec<&str> =
                   Regex::new(r" : ").unwrap().split(&nextLine).collect();
+                if data.len() < 2{
+                    errors.push(ParseError::line{
+                        number,
+                        reason: format!("expected '<artist> : <album>', got '{}'", nextLine)
+                    });
+                    album = Album::new(String::from("DEFAULT"), String::from("DEFALT"));
+                    continue;
+                }
                 album = Album::new(
                   String::from(data[1]),
                   String::from(data[0])
                 );
+                if let Some(date) = data.get(2){
+                    album.release_date = Some(parse_release_date(date));
+                }
             }
         }
+
+        if errors.is_empty(){
+            return Ok(());
+        }
+        return Err(errors);
     }
 
     fn find_album(&self, name: &str) -> Result<&Album, SearchError>{
@@ -186,6 +471,179 @@ impl Collection{
         self.find_album(album).expect("Couldn't locate Album.").displaySongs();
     }
 
+    /// Sorts this Collection's albums by artist, then chronologically by
+    /// release date (year, then month, then day).
+    ///
+    /// Albums with only a year are treated as having month and day `0`, so
+    /// they sort before dated releases in the same year rather than
+    /// ambiguously. Albums with no release date at all are treated as year
+    /// `0`, so they sort before every dated album by the same artist.
+    pub fn sort_albums(&mut self){
+        self.albums.sort_by_key(|album|{
+            let date = album.release_date.as_ref();
+            let year = date.map(|d| d.year).unwrap_or(0);
+            let month = date.map(|d| d.month).unwrap_or(0);
+            let day = date.map(|d| d.day).unwrap_or(0);
+            (album.artist.clone(), year, month, day)
+        });
+    }
+
+    /// Writes this Collection out as an extended M3U playlist file.
+    ///
+    /// The file starts with an `#EXTM3U` header, followed by one `#EXTINF`/
+    /// track pair per `Song` across every `Album` in the Collection, in
+    /// order. Each `#EXTINF` line carries the Song's length in seconds and a
+    /// display title of the form "<artist> - <name>"; the following line is
+    /// the track reference itself.
+    ///
+    /// Returns an `Err` if `path` can't be created or written to, rather
+    /// than panicking.
+    pub fn export_m3u(&self, path: String) -> std::io::Result<()>{
+        let mut file = File::create(&path)?;
+        write!(file, "#EXTM3U\n")?;
+        for album in &self.albums{
+            for song in &album.songs{
+                write!(file, "{}", song.to_m3u_entry())?;
+            }
+        }
+        return Ok(());
+    }
+
+    /// Builds a Collection by reading embedded tags out of every audio file
+    /// (mp3, flac, m4a, ogg) in `path`, rather than requiring a
+    /// hand-formatted `Albums.txt`.
+    ///
+    /// Tracks are grouped into `Album`s by their album tag, defaulting to
+    /// "Unknown Album"/"Unknown Artist" when those tags are missing; a
+    /// missing title tag falls back to the file's name.
+    ///
+    /// Returns an `Err` describing the problem if `path` can't be read at
+    /// all; an individual directory entry that can't be inspected is
+    /// skipped rather than aborting the whole scan.
+    pub fn from_audio_dir(path: String) -> Result<Collection, String>{
+        let mut collection = Collection::new();
+        let mut albums: Vec<Album> = Vec::new();
+
+        let entries = fs::read_dir(&path)
+          .map_err(|e| format!("couldn't read directory '{}': {}", path, e))?;
+
+        for entry in entries{
+            let file_path = match entry{
+                Ok(e) => e.path(),
+                Err(_) => continue
+            };
+            if !is_audio_file(&file_path){
+                continue;
+            }
+
+            let tags = read_tags(&file_path);
+            let title = tags.title.unwrap_or_else(||
+              file_path.file_stem().unwrap().to_string_lossy().to_string()
+            );
+            let artist = tags.artist.unwrap_or_else(|| String::from("Unknown Artist"));
+            let album_artist = tags.album_artist.clone().unwrap_or_else(|| artist.clone());
+            let album_name = tags.album.unwrap_or_else(|| String::from("Unknown Album"));
+            let mut song = Song::new(title, artist.clone(), tags.length);
+            song.track = tags.track;
+
+            match albums.iter_mut().find(|a| a.name == album_name){
+                Some(album) => album.add(song),
+                None => {
+                    let mut album = Album::new(album_name, album_artist);
+                    album.add(song);
+                    albums.push(album);
+                }
+            }
+        }
+
+        for album in albums{
+            collection.add(album);
+        }
+        return Ok(collection);
+    }
+
+    /// Builds a Collection from an artist/album directory tree, treating
+    /// each directory found between `min_depth` and `max_depth` levels
+    /// below `base` as one Album (the default depth of 2 expects
+    /// `base/Artist/Album`).
+    ///
+    /// Directories whose name starts with "extra" are skipped. Non-audio
+    /// files within an album directory (artwork, logs, liner notes, ...)
+    /// are ignored rather than becoming phantom tracks. Entries are walked
+    /// in sorted path order for deterministic output, and the number of
+    /// albums found so far is printed as they're discovered.
+    ///
+    /// A directory entry or album directory that can't be read (e.g. a
+    /// permissions error partway through the tree) is skipped rather than
+    /// aborting the whole scan.
+    pub fn scan_library_with_depth(base: &Path, min_depth: usize, max_depth: usize) -> Collection{
+        let mut collection = Collection::new();
+        let mut found: u32 = 0;
+
+        let walker = WalkDir::new(base)
+          .min_depth(min_depth)
+          .max_depth(max_depth)
+          .sort_by_file_name();
+
+        for entry in walker{
+            let entry = match entry{
+                Ok(e) => e,
+                Err(_) => continue
+            };
+            if !entry.file_type().is_dir(){
+                continue;
+            }
+            let dir_name = entry.file_name().to_string_lossy().to_string();
+            if dir_name.starts_with("extra"){
+                continue;
+            }
+
+            let artist = entry.path().parent()
+              .and_then(|p| p.file_name())
+              .map(|n| n.to_string_lossy().to_string())
+              .unwrap_or_else(|| String::from("Unknown Artist"));
+
+            let files = match fs::read_dir(entry.path()){
+                Ok(f) => f,
+                Err(_) => continue
+            };
+
+            let mut file_paths: Vec<_> = files
+              .filter_map(|f| f.ok())
+              .map(|f| f.path())
+              .collect();
+            file_paths.sort();
+
+            let mut album = Album::new(dir_name, artist);
+            for file_path in file_paths{
+                if !file_path.is_file() || !is_audio_file(&file_path){
+                    continue;
+                }
+                let tags = read_tags(&file_path);
+                let title = tags.title.unwrap_or_else(||
+                  file_path.file_stem().unwrap().to_string_lossy().to_string()
+                );
+                let song_artist = tags.artist.unwrap_or_else(|| album.artist.clone());
+                let mut song = Song::new(title, song_artist, tags.length);
+                song.track = tags.track;
+                album.add(song);
+            }
+
+            collection.add(album);
+            found += 1;
+            print!("Found {} album(s)\n", found);
+        }
+
+        return collection;
+    }
+
+    /// Builds a Collection from an artist/album directory tree at the
+    /// default depth of 2, complementing `parseFile` as an alternative way
+    /// to ingest an existing on-disk music hierarchy.
+    pub fn scan_library(base: &Path) -> Collection{
+        return Collection::scan_library_with_depth(base, 2, 2);
+    }
+
     /// Constructs a new Collection
     pub fn new() -> Collection{
         let new:Collection = Collection{
@@ -198,29 +656,167 @@ impl Collection{
 
 
 /// Converts a string representation of a time into its numerical equivalent.
-/// 
-/// Taking a string of the format "HH:MM:SS", this function returns that same
-/// time in seconds as a u16.
-/// 
+///
+/// Accepts "H:MM:SS", bare "MM:SS", or a lone "SS" value, returning that
+/// time in seconds as a u16. Returns an `Err` describing the problem
+/// instead of panicking when `time` isn't one of those forms.
+///
 /// # Examples
-/// 
+///
 /// ```
 /// let time: &str = String::from("00:1:20");
-/// print!("{}", to_seconds(time));
+/// print!("{}", to_seconds(time).unwrap());
 /// ```
-fn to_seconds(time: &str) -> u16{
+fn to_seconds(time: &str) -> Result<u16, String>{
     let values: Vec<&str> = Regex::new(r":").unwrap().split(time).collect();
-    let seconds: u16 = (
-      (values[0].parse::<u16>().unwrap() * 60 * 60) +
-      (values[1].parse::<u16>().unwrap() * 60) +
-      values[2].parse::<u16>().unwrap()
-    );
-    return seconds;
+    let parsed: Vec<u32> = values.iter()
+      .map(|v| v.parse::<u32>())
+      .collect::<Result<Vec<u32>, _>>()
+      .map_err(|_| format!("'{}' is not a valid duration", time))?;
+
+    let overflows = || format!("'{}' is too long a duration to represent", time);
+    let seconds: u32 = match parsed.as_slice(){
+        [hours, minutes, secs] => hours.checked_mul(3600)
+          .and_then(|h| minutes.checked_mul(60).and_then(|m| h.checked_add(m)))
+          .and_then(|hm| hm.checked_add(*secs))
+          .ok_or_else(overflows)?,
+        [minutes, secs] => minutes.checked_mul(60)
+          .and_then(|m| m.checked_add(*secs))
+          .ok_or_else(overflows)?,
+        [secs] => *secs,
+        _ => return Err(format!("'{}' is not a valid duration", time))
+    };
+
+    return u16::try_from(seconds).map_err(|_| overflows());
 }
 
 fn to_timestamp(seconds: u16) -> String{
     let hours: u16 = seconds / 3600;
     let minutes: u16 = (seconds % 3600) / 60;
     let seconds: u16 = (seconds % 3600) % 60;
-    return format!("{}:{}:{}", hours, minutes, seconds);
+    return format!("{}:{:02}:{:02}", hours, minutes, seconds);
+}
+
+
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn to_seconds_parses_h_mm_ss(){
+        assert_eq!(to_seconds("1:02:03"), Ok(3723));
+    }
+
+    #[test]
+    fn to_seconds_parses_bare_mm_ss(){
+        assert_eq!(to_seconds("02:03"), Ok(123));
+    }
+
+    #[test]
+    fn to_seconds_parses_bare_seconds(){
+        assert_eq!(to_seconds("45"), Ok(45));
+    }
+
+    #[test]
+    fn to_seconds_rejects_non_numeric_input(){
+        assert!(to_seconds("not-a-time").is_err());
+    }
+
+    #[test]
+    fn to_seconds_rejects_overflowing_input(){
+        assert!(to_seconds("9999:99").is_err());
+        assert!(to_seconds("19:00:00").is_err());
+    }
+
+    #[test]
+    fn to_seconds_rejects_input_overflowing_the_u32_intermediate(){
+        assert!(to_seconds("4294967295:0:0").is_err());
+    }
+
+    #[test]
+    fn parse_track_position_drops_album_size(){
+        assert_eq!(parse_track_position("3/12"), Some(3));
+    }
+
+    #[test]
+    fn parse_track_position_accepts_bare_track_number(){
+        assert_eq!(parse_track_position("7"), Some(7));
+    }
+
+    #[test]
+    fn lrc_tag_to_centiseconds_parses_hundredths(){
+        assert_eq!(lrc_tag_to_centiseconds("01:02.50"), Some(6250));
+    }
+
+    #[test]
+    fn lrc_tag_to_centiseconds_defaults_missing_hundredths(){
+        assert_eq!(lrc_tag_to_centiseconds("00:10"), Some(1000));
+    }
+
+    #[test]
+    fn lrc_tag_to_centiseconds_normalizes_single_digit_fraction(){
+        assert_eq!(lrc_tag_to_centiseconds("00:10.5"), Some(1050));
+    }
+
+    #[test]
+    fn lrc_tag_to_centiseconds_normalizes_millisecond_fraction(){
+        assert_eq!(lrc_tag_to_centiseconds("00:10.500"), Some(1050));
+    }
+
+    #[test]
+    fn lyric_at_returns_last_line_at_or_before_position(){
+        let mut song = Song::new(String::from("Song"), String::from("Artist"), 180);
+        song.lyrics = Some(vec![
+            (0, String::from("first")),
+            (500, String::from("second")),
+            (1000, String::from("third"))
+        ]);
+
+        assert_eq!(song.lyric_at(0.0), Some("first"));
+        assert_eq!(song.lyric_at(6.0), Some("second"));
+        assert_eq!(song.lyric_at(999.0), Some("third"));
+    }
+
+    #[test]
+    fn lyric_at_returns_none_before_first_line(){
+        let mut song = Song::new(String::from("Song"), String::from("Artist"), 180);
+        song.lyrics = Some(vec![(100, String::from("first"))]);
+
+        assert_eq!(song.lyric_at(0.5), None);
+    }
+
+    #[test]
+    fn parse_release_date_parses_full_date(){
+        let date = parse_release_date("2021-03-05");
+        assert_eq!((date.year, date.month, date.day), (2021, 3, 5));
+    }
+
+    #[test]
+    fn parse_release_date_defaults_missing_month_and_day(){
+        let date = parse_release_date("2021");
+        assert_eq!((date.year, date.month, date.day), (2021, 0, 0));
+    }
+
+    #[test]
+    fn sort_albums_orders_chronologically_breaking_ties_by_month_then_day(){
+        let mut collection = Collection::new();
+
+        let mut later = Album::new(String::from("Later"), String::from("Artist"));
+        later.release_date = Some(ReleaseDate{year: 2021, month: 3, day: 0});
+        collection.add(later);
+
+        let mut earlier = Album::new(String::from("Earlier"), String::from("Artist"));
+        earlier.release_date = Some(ReleaseDate{year: 2021, month: 1, day: 15});
+        collection.add(earlier);
+
+        let mut year_only = Album::new(String::from("YearOnly"), String::from("Artist"));
+        year_only.release_date = Some(ReleaseDate{year: 2021, month: 0, day: 0});
+        collection.add(year_only);
+
+        collection.sort_albums();
+
+        let names: Vec<&str> = collection.albums.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["YearOnly", "Earlier", "Later"]);
+    }
 }